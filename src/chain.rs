@@ -0,0 +1,79 @@
+#[cfg(feature = "std")]
+use std::string::{String, ToString};
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+use alloc::string::{String, ToString};
+
+use crate::private::Error;
+
+/// Iterate over an error and the chain of `source()`s behind it.
+///
+/// ## Examples
+///
+/// ```rust
+/// use adhocerr::chain;
+/// use adhocerr::wrap;
+/// # use std::error::Error;
+///
+/// fn parse(input: &str) -> Result<u32, impl Error + 'static> {
+///     input.parse().map_err(wrap!("Failed to parse input"))
+/// }
+///
+/// if let Err(e) = parse("not a number") {
+///     for cause in chain(&e) {
+///         println!("{cause}");
+///     }
+/// }
+/// ```
+pub fn chain<'a>(err: &'a (dyn Error + 'static)) -> Chain<'a> {
+    Chain { current: Some(err) }
+}
+
+/// An iterator over an error and the chain of `source()`s behind it, yielding the error itself
+/// first and then repeatedly calling `Error::source()` until it returns `None`.
+pub struct Chain<'a> {
+    current: Option<&'a (dyn Error + 'static)>,
+}
+
+impl<'a> Iterator for Chain<'a> {
+    type Item = &'a (dyn Error + 'static);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current?;
+        self.current = current.source();
+        Some(current)
+    }
+}
+
+/// Join an error and its chain of causes into a single `"root: cause: cause"` string.
+///
+/// Requires the `std` or `alloc` feature, since it allocates the joined `String`.
+///
+/// ## Examples
+///
+/// ```rust
+/// use adhocerr::chain_to_string;
+/// use adhocerr::wrap;
+/// # use std::error::Error;
+///
+/// fn parse(input: &str) -> Result<u32, impl Error + 'static> {
+///     input.parse().map_err(wrap!("Failed to parse input"))
+/// }
+///
+/// if let Err(e) = parse("not a number") {
+///     assert!(chain_to_string(&e).starts_with("Failed to parse input: "));
+/// }
+/// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub fn chain_to_string(err: &(dyn Error + 'static)) -> String {
+    let mut joined = String::new();
+
+    for (i, cause) in chain(err).enumerate() {
+        if i > 0 {
+            joined.push_str(": ");
+        }
+
+        joined.push_str(&cause.to_string());
+    }
+
+    joined
+}
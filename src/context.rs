@@ -0,0 +1,72 @@
+use core::fmt;
+
+use crate::private::Error;
+
+/// Extension trait providing `.context(..)` as a fluent alternative to `.map_err(wrap!(..))`
+///
+/// ## Examples
+///
+/// ```rust
+/// use adhocerr::Context;
+/// # use std::{error::Error, path::Path};
+///
+/// fn record_success(file: &Path) -> Result<(), impl Error + 'static> {
+///     std::fs::write(file, "true").context("Failed to save results of script")
+/// }
+/// ```
+///
+/// Deferring the message until the error path is taken:
+///
+/// ```rust
+/// use adhocerr::Context;
+/// # use std::{error::Error, path::Path};
+///
+/// fn record_success(file: &Path) -> Result<(), impl Error + 'static + use<'_>> {
+///     std::fs::write(file, "true")
+///         .with_context(|| format!("Failed to save results of script to {}", file.display()))
+/// }
+/// ```
+pub trait Context<T, E> {
+    /// Wrap the error in `self`, if any, with a context message built from `context`.
+    fn context<C>(self, context: C) -> Result<T, impl Error + Send + Sync + 'static>
+    where
+        C: fmt::Display + Send + Sync + 'static;
+
+    /// Wrap the error in `self`, if any, with a context message built lazily by `context`.
+    ///
+    /// The closure is only invoked on the error path, so it's suited to messages that
+    /// aren't free to construct.
+    ///
+    /// Because this method is generic over the closure type `F`, the opaque return type
+    /// captures whatever lifetimes `F` happens to borrow, even though the returned error never
+    /// borrows them itself. If `context` closes over borrowed data (as in the example above),
+    /// the caller's own `impl Error` return type must explicitly capture that lifetime with
+    /// `+ use<'_>` (or name it), or the compiler will reject it with E0700.
+    fn with_context<C, F>(self, context: F) -> Result<T, impl Error + Send + Sync + 'static>
+    where
+        C: fmt::Display + Send + Sync + 'static,
+        F: FnOnce() -> C;
+}
+
+impl<T, E> Context<T, E> for Result<T, E>
+where
+    E: Error + Send + Sync + 'static,
+{
+    fn context<C>(self, context: C) -> Result<T, impl Error + Send + Sync + 'static>
+    where
+        C: fmt::Display + Send + Sync + 'static,
+    {
+        self.map_err(|source| crate::private::format_wrap_err(source, format_args!("{context}")))
+    }
+
+    fn with_context<C, F>(self, context: F) -> Result<T, impl Error + Send + Sync + 'static>
+    where
+        C: fmt::Display + Send + Sync + 'static,
+        F: FnOnce() -> C,
+    {
+        self.map_err(|source| {
+            let context = context();
+            crate::private::format_wrap_err(source, format_args!("{context}"))
+        })
+    }
+}
@@ -57,6 +57,27 @@
 //! uses pre defined Error types to wrap the String to avoid declaring new types
 //! unnecessarily, but hides them behind an impl Trait boundary.
 //!
+//! ### `no_std` support
+//!
+//! This crate is `no_std` compatible. Disable the default `std` feature to
+//! build against `core::error::Error` instead of `std::error::Error`. The
+//! static-string arms of `err!`/`wrap!`/`ensure!`/`bail!` declare their own
+//! error type (a ZST, or for `wrap!` a wrapper the size of the wrapped error)
+//! and need neither `std` nor `alloc`, so they work in a pure `no_std`, no-alloc
+//! context (e.g. a `#![no_std]` binary with no global allocator). The
+//! interpolating arms, `Context`, `chain_to_string`, and `aggregate!`/
+//! `MultiError` all build a `String` or `Vec`/`Box`, so they additionally
+//! require either `std` or the separate `alloc` feature.
+//!
+//! ### Backtraces
+//!
+//! Enabling the `backtrace` feature (which requires `std` and a nightly
+//! compiler, for `std::error::Error::provide`) makes the interpolating arms
+//! of `err!`/`wrap!` capture a `std::backtrace::Backtrace` honoring
+//! `RUST_BACKTRACE`/`RUST_LIB_BACKTRACE`, retrievable via
+//! `std::error::request_ref`. The static-ZST arms are unaffected and stay
+//! zero-sized.
+//!
 //! ### Expanded
 //!
 //! The Expanded version of the example above would look like this:
@@ -91,8 +112,37 @@
 //!         })
 //! }
 //! ```
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(
+    all(feature = "backtrace", feature = "std"),
+    feature(error_generic_member_access)
+)]
+
+#[cfg(all(feature = "backtrace", not(feature = "std")))]
+compile_error!("the `backtrace` feature requires the `std` feature");
+
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+extern crate alloc;
+
+#[cfg(any(feature = "std", feature = "alloc"))]
 use core::fmt;
+#[cfg(any(feature = "std", feature = "alloc"))]
+use private::Error;
+
+mod chain;
+#[cfg(any(feature = "std", feature = "alloc"))]
+mod context;
+#[cfg(any(feature = "std", feature = "alloc"))]
+mod multi_error;
+
+pub use chain::{chain, Chain};
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub use chain::chain_to_string;
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub use context::Context;
 pub use err as format_err;
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub use multi_error::{collect_errors, MultiError};
 
 /// Thinly wrap an error by defining a hidden error type and returning a closure to construct it
 ///
@@ -182,18 +232,18 @@ macro_rules! wrap {
             source: E,
         }
 
-        impl<E> std::error::Error for WrappedError<E>
+        impl<E> $crate::private::Error for WrappedError<E>
         where
-            E: std::error::Error + 'static,
+            E: $crate::private::Error + 'static,
         {
-            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            fn source(&self) -> Option<&(dyn $crate::private::Error + 'static)> {
                 Some(&self.source)
             }
         }
 
         impl<E> core::fmt::Display for WrappedError<E>
         where
-            E: std::error::Error + 'static,
+            E: $crate::private::Error + 'static,
         {
             fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
                 f.write_str($msg)
@@ -307,8 +357,8 @@ macro_rules! err {
         #[derive(Debug)]
         struct AdhocError;
 
-        impl std::error::Error for AdhocError {
-            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        impl $crate::private::Error for AdhocError {
+            fn source(&self) -> Option<&(dyn $crate::private::Error + 'static)> {
                 None
             }
         }
@@ -326,40 +376,67 @@ macro_rules! err {
     };
 }
 
+#[cfg(feature = "std")]
+use std::string::String;
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+use alloc::string::String;
+
+#[cfg(any(feature = "std", feature = "alloc"))]
 #[derive(Debug)]
-struct FormatError(String);
+struct FormatError {
+    msg: String,
+    #[cfg(all(feature = "backtrace", feature = "std"))]
+    backtrace: std::backtrace::Backtrace,
+}
 
+#[cfg(any(feature = "std", feature = "alloc"))]
 impl fmt::Display for FormatError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.0.fmt(f)
+        self.msg.fmt(f)
     }
 }
 
-impl std::error::Error for FormatError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl Error for FormatError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
         None
     }
+
+    #[cfg(all(feature = "backtrace", feature = "std"))]
+    fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+        request.provide_ref::<std::backtrace::Backtrace>(&self.backtrace);
+    }
 }
 
+#[cfg(any(feature = "std", feature = "alloc"))]
 #[derive(Debug)]
 struct FormatWrappedError<E> {
     msg: String,
     source: E,
+    #[cfg(all(feature = "backtrace", feature = "std"))]
+    backtrace: std::backtrace::Backtrace,
 }
 
+#[cfg(any(feature = "std", feature = "alloc"))]
 impl<E> fmt::Display for FormatWrappedError<E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.msg.fmt(f)
     }
 }
 
-impl<E> std::error::Error for FormatWrappedError<E>
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<E> Error for FormatWrappedError<E>
 where
-    E: std::error::Error + 'static,
+    E: Error + 'static,
 {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
         Some(&self.source)
     }
+
+    #[cfg(all(feature = "backtrace", feature = "std"))]
+    fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+        request.provide_ref::<std::backtrace::Backtrace>(&self.backtrace);
+    }
 }
 
 /// Return early with an error if a condition is not satisfied.
@@ -395,6 +472,24 @@ where
 ///     Ok(())
 /// }
 /// ```
+///
+/// `ensure!` also accepts an arbitrary error expression in place of a message,
+/// coercing it through `From`/`Into` into the function's declared error type:
+///
+/// ```
+/// use adhocerr::ensure;
+/// use std::io;
+///
+/// fn main() -> Result<(), io::Error> {
+/// #     let user = 0;
+/// #
+///     ensure!(
+///         user == 0,
+///         io::Error::new(io::ErrorKind::PermissionDenied, "only user 0 is allowed")
+///     );
+///     Ok(())
+/// }
+/// ```
 #[macro_export]
 macro_rules! ensure {
     ($cond:expr, $msg:literal) => {
@@ -417,6 +512,11 @@ macro_rules! ensure {
             return $crate::private::Err($crate::err!($fmt, $($arg)*).into());
         }
     };
+    ($cond:expr, $err:expr) => {
+        if !$cond {
+            return $crate::private::Err(From::from($err));
+        }
+    };
 }
 
 /// Return an ad-hoc error immediately
@@ -459,6 +559,32 @@ macro_rules! ensure {
 ///     Ok(())
 /// }
 /// ```
+///
+/// `bail!` also accepts an arbitrary error expression in place of a message,
+/// coercing it through `From`/`Into` into the function's declared error type:
+///
+/// ```
+/// use adhocerr::bail;
+///
+/// # enum MyError { NotFound }
+/// # impl std::fmt::Debug for MyError {
+/// #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "MyError") }
+/// # }
+/// # impl std::fmt::Display for MyError {
+/// #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "not found") }
+/// # }
+/// # impl std::error::Error for MyError {}
+/// #
+/// fn main() -> Result<(), MyError> {
+/// #     let found = true;
+/// #
+///     if !found {
+///         bail!(MyError::NotFound);
+///     }
+///
+///     Ok(())
+/// }
+/// ```
 #[macro_export]
 macro_rules! bail {
     ($msg:literal) => {
@@ -473,30 +599,54 @@ macro_rules! bail {
     ($fmt:literal, $($arg:tt)*?) => {
         return $crate::private::Err($crate::err!($fmt, $($arg)*).into());
     };
+    ($err:expr) => {
+        return $crate::private::Err(From::from($err));
+    };
 }
 
 #[doc(hidden)]
 pub mod private {
     pub use core::result::Result::Err;
 
-    pub fn format_err(
-        args: core::fmt::Arguments<'_>,
-    ) -> impl std::error::Error + Send + Sync + 'static {
-        crate::FormatError(args.to_string())
+    #[cfg(feature = "std")]
+    pub use std::error::Error;
+    #[cfg(not(feature = "std"))]
+    pub use core::error::Error;
+
+    #[cfg(feature = "std")]
+    pub use std::boxed::Box;
+    #[cfg(all(not(feature = "std"), feature = "alloc"))]
+    pub use alloc::boxed::Box;
+
+    #[cfg(feature = "std")]
+    use std::string::ToString;
+    #[cfg(all(not(feature = "std"), feature = "alloc"))]
+    use alloc::string::ToString;
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn format_err(args: core::fmt::Arguments<'_>) -> impl Error + Send + Sync + 'static {
+        crate::FormatError {
+            msg: args.to_string(),
+            #[cfg(all(feature = "backtrace", feature = "std"))]
+            backtrace: std::backtrace::Backtrace::capture(),
+        }
     }
 
+    #[cfg(any(feature = "std", feature = "alloc"))]
     pub fn format_wrap_err(
-        source: impl std::error::Error + Send + Sync + 'static,
+        source: impl Error + Send + Sync + 'static,
         args: core::fmt::Arguments<'_>,
-    ) -> impl std::error::Error + Send + Sync + 'static {
+    ) -> impl Error + Send + Sync + 'static {
         crate::FormatWrappedError {
             msg: args.to_string(),
             source,
+            #[cfg(all(feature = "backtrace", feature = "std"))]
+            backtrace: std::backtrace::Backtrace::capture(),
         }
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
@@ -527,4 +677,17 @@ mod tests {
     fn ensure_impl() {
         try_code_impl().unwrap();
     }
+
+    #[test]
+    #[cfg(feature = "backtrace")]
+    fn backtrace_is_retrievable_from_dynamic_errors() {
+        let err: Box<dyn std::error::Error + 'static> = Box::new(err!("boom: {}", 1));
+        assert!(std::error::request_ref::<std::backtrace::Backtrace>(err.as_ref()).is_some());
+
+        let result: Result<i32, _> = "not a number"
+            .parse::<i32>()
+            .map_err(wrap!("failed to parse: {}", "not a number"));
+        let wrapped: Box<dyn std::error::Error + 'static> = Box::new(result.unwrap_err());
+        assert!(std::error::request_ref::<std::backtrace::Backtrace>(wrapped.as_ref()).is_some());
+    }
 }
@@ -0,0 +1,133 @@
+#[cfg(feature = "std")]
+use std::{boxed::Box, vec::Vec};
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+use alloc::{boxed::Box, vec::Vec};
+
+use core::fmt;
+
+use crate::private::Error;
+
+/// Aggregate the given results into a single `Result<Vec<_>, MultiError>`.
+///
+/// Every expression is evaluated, so unlike `ensure!`/`bail!` this does not return early on the
+/// first failure; it collects all of them into one [`MultiError`]. Each error is boxed to
+/// `Box<dyn Error + Send + Sync + 'static>` before being collected, so the results don't all have
+/// to share the same concrete/opaque error type — joining results from unrelated checks works
+/// just as well as calling the same function repeatedly.
+///
+/// ## Examples
+///
+/// ```rust
+/// use adhocerr::{aggregate, ensure};
+///
+/// fn is_positive(n: i32) -> Result<i32, impl std::error::Error + 'static> {
+///     ensure!(n > 0, "{} must be positive", n);
+///     Ok(n)
+/// }
+///
+/// fn is_even(n: i32) -> Result<i32, impl std::error::Error + 'static> {
+///     ensure!(n % 2 == 0, "{} must be even", n);
+///     Ok(n)
+/// }
+///
+/// let result = aggregate!(is_positive(1), is_positive(-1), is_even(-1));
+/// assert_eq!(result.unwrap_err().len(), 2);
+/// ```
+#[macro_export]
+macro_rules! aggregate {
+    ($($result:expr),+ $(,)?) => {
+        $crate::collect_errors([
+            $(
+                ($result).map_err(|err| -> $crate::private::Box<
+                    dyn $crate::private::Error + Send + Sync + 'static,
+                > { $crate::private::Box::new(err) })
+            ),+
+        ])
+    };
+}
+
+/// Drain an iterator of `Result`s, collecting every `Ok` value if all succeed, or every `Err`
+/// into a [`MultiError`] if any fail.
+///
+/// `E` is bounded by `Into<Box<dyn Error + Send + Sync + 'static>>` rather than `Error` itself:
+/// `Box<dyn Error + Send + Sync>` doesn't implement `Error` (there's no blanket impl for a boxed
+/// trait object, only `From`/`Into` it), so requiring `E: Error` here would reject the very
+/// pre-boxed, heterogeneous errors that [`aggregate!`](crate::aggregate) passes in. Any concrete
+/// `E: Error + Send + Sync + 'static` still satisfies the bound through std's blanket
+/// `From<E> for Box<dyn Error + Send + Sync>` impl, so this is no less permissive for callers
+/// with a single concrete error type.
+pub fn collect_errors<T, E, I>(results: I) -> Result<Vec<T>, MultiError>
+where
+    I: IntoIterator<Item = Result<T, E>>,
+    E: Into<Box<dyn Error + Send + Sync + 'static>>,
+{
+    let mut values = Vec::new();
+    let mut errors = MultiError::new();
+
+    for result in results {
+        match result {
+            Ok(value) => values.push(value),
+            Err(err) => errors.errors.push(err.into()),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(values)
+    } else {
+        Err(errors)
+    }
+}
+
+/// An error type that aggregates several independent errors, for operations that want to report
+/// every problem they found rather than stopping at the first one.
+#[derive(Debug, Default)]
+pub struct MultiError {
+    errors: Vec<Box<dyn Error + Send + Sync + 'static>>,
+}
+
+impl MultiError {
+    /// Create an empty `MultiError`.
+    pub fn new() -> Self {
+        Self { errors: Vec::new() }
+    }
+
+    /// Add an error to the collection.
+    pub fn push(&mut self, err: impl Error + Send + Sync + 'static) {
+        self.errors.push(Box::new(err));
+    }
+
+    /// The number of errors collected.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Whether no errors have been collected.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The individual errors that were collected.
+    pub fn errors(&self) -> &[Box<dyn Error + Send + Sync + 'static>] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for MultiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} error(s) occurred:", self.errors.len())?;
+
+        for (i, err) in self.errors.iter().enumerate() {
+            writeln!(f, "  {}: {}", i + 1, err)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for MultiError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.errors
+            .first()
+            .map(|err| err.as_ref() as &(dyn Error + 'static))
+    }
+}